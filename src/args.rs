@@ -0,0 +1,190 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+
+const MAX_FRAME_INTERVAL: Duration = Duration::from_secs(3600);
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LoopMode {
+    Poll,
+    #[default]
+    Wait,
+    WaitUntil,
+}
+
+#[derive(Default)]
+pub struct Args {
+    pub exit_after: Option<Duration>,
+    pub confirm_exit: bool,
+    pub fullscreen: bool,
+    pub exclusive: Option<(u32, u32)>,
+    pub delay: Duration,
+    pub loop_mode: LoopMode,
+    pub frame_interval: Option<Duration>,
+}
+
+impl Args {
+    pub fn parse() -> Result<Self> {
+        Self::parse_from(std::env::args().skip(1))
+    }
+
+    pub fn parse_from(mut it: impl Iterator<Item = String>) -> Result<Self> {
+        let mut args = Args::default();
+        let mut loop_mode = None;
+
+        while let Some(arg) = it.next() {
+            match arg.as_str() {
+                // exit the event loop after the given number of seconds
+                "--exit-after" => {
+                    let secs: f64 = value(&mut it, &arg)?
+                        .parse()
+                        .with_context(|| format!("invalid value for {}", arg))?;
+                    args.exit_after = Some(
+                        Duration::try_from_secs_f64(secs)
+                            .with_context(|| format!("invalid value for {}", arg))?,
+                    );
+                }
+                // ask for a second Escape/close before closing the last window
+                "--confirm-exit" => args.confirm_exit = true,
+                // start the first window in borderless fullscreen
+                "--fullscreen" => args.fullscreen = true,
+                // start the first window in exclusive fullscreen at WxH
+                "--exclusive" => args.exclusive = Some(size(&value(&mut it, &arg)?, &arg)?),
+                // wait the given number of milliseconds before creating the first window
+                "--delay" => {
+                    let ms = value(&mut it, &arg)?
+                        .parse()
+                        .with_context(|| format!("invalid value for {}", arg))?;
+                    args.delay = Duration::from_millis(ms);
+                }
+                // event loop control flow strategy
+                "--loop" => {
+                    loop_mode = Some(match value(&mut it, &arg)?.as_str() {
+                        "poll" => LoopMode::Poll,
+                        "wait" => LoopMode::Wait,
+                        "wait-until" => LoopMode::WaitUntil,
+                        other => {
+                            return Err(anyhow!(
+                                "invalid value for {}: {} (expected poll, wait or wait-until)",
+                                arg,
+                                other
+                            ))
+                        }
+                    });
+                }
+                // frame rate for --loop wait-until, which it implies
+                "--fps" => {
+                    let fps: f64 = value(&mut it, &arg)?
+                        .parse()
+                        .with_context(|| format!("invalid value for {}", arg))?;
+                    if !fps.is_finite() || fps <= 0.0 {
+                        return Err(anyhow!("invalid value for {}: must be positive", arg));
+                    }
+                    let interval = Duration::try_from_secs_f64(1.0 / fps)
+                        .with_context(|| format!("invalid value for {}", arg))?;
+                    if interval.is_zero() || interval > MAX_FRAME_INTERVAL {
+                        return Err(anyhow!(
+                            "invalid value for {}: must be between one frame per hour and one frame per nanosecond",
+                            arg
+                        ));
+                    }
+                    args.frame_interval = Some(interval);
+                }
+                _ => return Err(anyhow!("unknown argument: {}", arg)),
+            }
+        }
+
+        if args.fullscreen && args.exclusive.is_some() {
+            return Err(anyhow!(
+                "--fullscreen and --exclusive cannot be used together"
+            ));
+        }
+
+        args.loop_mode = match (loop_mode, args.frame_interval) {
+            (None, Some(_)) => LoopMode::WaitUntil,
+            (Some(mode), Some(_)) if mode != LoopMode::WaitUntil => {
+                return Err(anyhow!("--fps can only be used with --loop wait-until"))
+            }
+            (mode, _) => mode.unwrap_or_default(),
+        };
+
+        Ok(args)
+    }
+}
+
+fn value(it: &mut impl Iterator<Item = String>, arg: &str) -> Result<String> {
+    it.next()
+        .ok_or_else(|| anyhow!("missing value for {}", arg))
+}
+
+fn size(value: &str, arg: &str) -> Result<(u32, u32)> {
+    let (w, h) = value
+        .split_once('x')
+        .ok_or_else(|| anyhow!("invalid value for {}: expected WxH", arg))?;
+    let w = w
+        .parse()
+        .with_context(|| format!("invalid width for {}", arg))?;
+    let h = h
+        .parse()
+        .with_context(|| format!("invalid height for {}", arg))?;
+    if w == 0 || h == 0 {
+        return Err(anyhow!("invalid value for {}: size must not be zero", arg));
+    }
+    Ok((w, h))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args> {
+        Args::parse_from(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn fps_implies_wait_until() {
+        let args = parse(&["--fps", "30"]).unwrap();
+        assert_eq!(args.loop_mode, LoopMode::WaitUntil);
+        assert_eq!(
+            args.frame_interval,
+            Some(Duration::from_secs_f64(1.0 / 30.0))
+        );
+
+        let args = parse(&["--loop", "wait-until", "--fps", "30"]).unwrap();
+        assert_eq!(args.loop_mode, LoopMode::WaitUntil);
+    }
+
+    #[test]
+    fn fps_rejected_with_other_loop_modes() {
+        assert!(parse(&["--fps", "30", "--loop", "poll"]).is_err());
+        assert!(parse(&["--loop", "wait", "--fps", "30"]).is_err());
+    }
+
+    #[test]
+    fn fps_out_of_range_rejected() {
+        assert!(parse(&["--fps", "0"]).is_err());
+        assert!(parse(&["--fps", "inf"]).is_err());
+        assert!(parse(&["--fps", "1e-9"]).is_err());
+        assert!(parse(&["--fps", "1e10"]).is_err());
+    }
+
+    #[test]
+    fn exit_after_rejects_nan_and_negative() {
+        assert!(parse(&["--exit-after", "NaN"]).is_err());
+        assert!(parse(&["--exit-after", "-1"]).is_err());
+        let args = parse(&["--exit-after", "1.5"]).unwrap();
+        assert_eq!(args.exit_after, Some(Duration::from_millis(1500)));
+    }
+
+    #[test]
+    fn exclusive_size() {
+        assert_eq!(
+            parse(&["--exclusive", "1920x1080"]).unwrap().exclusive,
+            Some((1920, 1080))
+        );
+        assert!(parse(&["--exclusive", "1920x"]).is_err());
+        assert!(parse(&["--exclusive", "1920"]).is_err());
+        assert!(parse(&["--exclusive", "0x1080"]).is_err());
+        assert!(parse(&["--fullscreen", "--exclusive", "1920x1080"]).is_err());
+    }
+}
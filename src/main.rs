@@ -1,14 +1,20 @@
-use std::sync::{atomic::Ordering, Arc};
+use std::{
+    sync::{atomic::Ordering, Arc},
+    time::{Duration, Instant},
+};
 
 use winit::{
     event::*,
-    event_loop::{EventLoop, EventLoopWindowTarget},
-    keyboard::PhysicalKey,
-    window::WindowBuilder,
+    event_loop::{ControlFlow, EventLoop, EventLoopWindowTarget},
+    keyboard::{KeyCode, PhysicalKey},
+    window::{Fullscreen, WindowBuilder, WindowId},
 };
 
 use anyhow::{anyhow, Result};
 
+mod args;
+use args::{Args, LoopMode};
+
 mod window;
 use window::ZxWindow;
 
@@ -17,7 +23,25 @@ use cmd::CmdQueue;
 
 use crate::{cmd::WindowCmd, window::ZxState};
 
-pub fn run() -> Result<()> {
+const CONFIRM_EXIT_TIMEOUT: Duration = Duration::from_secs(3);
+const DEFAULT_FRAME_INTERVAL: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+struct PendingExit {
+    deadline: Instant,
+    window_id: WindowId,
+    title: String,
+}
+
+pub fn run(args: Args) -> Result<()> {
+    let exit_at = args
+        .exit_after
+        .map(|d| {
+            Instant::now()
+                .checked_add(d)
+                .ok_or_else(|| anyhow!("invalid value for --exit-after: too large"))
+        })
+        .transpose()?;
+
     let event_loop = EventLoop::new()?;
 
     println!("event_loop created");
@@ -25,6 +49,11 @@ pub fn run() -> Result<()> {
     let mut started = false;
     let mut windows: Vec<ZxWindow> = vec![];
     let shared_state = Arc::new(ZxState::default());
+    let mut pending_exit: Option<PendingExit> = None;
+    let mut initial_fullscreen: Option<Fullscreen> = None;
+    let mut create_at: Option<Instant> = None;
+    let frame_interval = args.frame_interval.unwrap_or(DEFAULT_FRAME_INTERVAL);
+    let mut next_frame = Instant::now();
 
     let event_handler = move |event, target: &EventLoopWindowTarget<()>| {
         let q = CmdQueue::new();
@@ -43,20 +72,23 @@ pub fn run() -> Result<()> {
                     WindowEvent::CloseRequested => {
                         println!("\tWindowEvent");
                         println!("\t\tCloseRequested");
-                        windows.remove(idx);
+                        windows[idx].set_exiting(true);
                     }
                     WindowEvent::KeyboardInput {
                         event,
                         is_synthetic,
                         ..
-                    } => match event.physical_key {
-                        PhysicalKey::Code(code) => {
+                    } => {
+                        if let PhysicalKey::Code(code) = event.physical_key {
                             println!("\tWindowEvent");
                             println!(
                                 "\t\tKeyboardInput {:?} - {:?} - {}",
                                 code, event.state, is_synthetic
                             );
-                            if !is_synthetic {
+                            // a held Escape must not confirm a pending exit on its own
+                            let repeated_escape =
+                                event.repeat && code == KeyCode::Escape && args.confirm_exit;
+                            if !is_synthetic && !repeated_escape {
                                 windows[idx].on_key_input(
                                     code,
                                     event.state == ElementState::Pressed,
@@ -64,8 +96,7 @@ pub fn run() -> Result<()> {
                                 );
                             }
                         }
-                        _ => {}
-                    },
+                    }
                     // WindowEvent::ActivationTokenDone { serial, token } => todo!(),
                     WindowEvent::Resized(new_size) => {
                         println!("\t\tResized {:?}", new_size);
@@ -125,20 +156,73 @@ pub fn run() -> Result<()> {
             }
             Event::Resumed => {
                 println!("\tResumed started={}", started);
-                if !started {
+                if !started && create_at.is_none() {
+                    // the first window is created in AboutToWait once --delay has elapsed
+                    create_at = Some(Instant::now() + args.delay);
+                }
+            }
+            Event::AboutToWait => {
+                if started && windows.is_empty() {
+                    // no more window ... exiting .. unless we have a system tray icon ???
+                    target.exit();
+                }
+
+                if create_at.is_some_and(|t| Instant::now() >= t) {
+                    if !args.delay.is_zero() {
+                        println!("\tdelay elapsed, creating window");
+                    }
+                    create_at = None;
                     started = true;
+                    initial_fullscreen = initial_fullscreen_mode(&args, target);
 
                     q.add(WindowCmd::CreateWindow(format!(
                         "window {}",
                         shared_state.count.fetch_add(1, Ordering::Relaxed) + 1
                     )));
                 }
-            }
-            Event::AboutToWait => {
-                if started && windows.len() == 0 {
-                    // no more window ... exiting .. unless we have a system tray icon ???
+
+                if exit_at.is_some_and(|t| Instant::now() >= t) {
+                    println!("\texit-after elapsed, exiting");
                     target.exit();
                 }
+
+                if pending_exit
+                    .as_ref()
+                    .is_some_and(|p| Instant::now() >= p.deadline)
+                {
+                    println!("\texit not confirmed, cancelled");
+                    cancel_pending_exit(&mut pending_exit, &windows);
+                }
+
+                // poll redraws every iteration, wait-until once per frame interval
+                let now = Instant::now();
+                if args.loop_mode != LoopMode::Wait && now >= next_frame {
+                    for window in &windows {
+                        window.request_redraw();
+                    }
+                    if args.loop_mode == LoopMode::WaitUntil {
+                        // keep the cadence when on time, restart it after a stall
+                        next_frame += frame_interval;
+                        if next_frame <= now {
+                            next_frame = now + frame_interval;
+                        }
+                    }
+                }
+                let frame_at = (args.loop_mode == LoopMode::WaitUntil).then_some(next_frame);
+
+                // wake up even if no event comes in
+                let confirm_at = pending_exit.as_ref().map(|p| p.deadline);
+                let deadline = [exit_at, confirm_at, create_at, frame_at]
+                    .into_iter()
+                    .flatten()
+                    .min();
+                match (args.loop_mode, deadline) {
+                    (LoopMode::Poll, _) => target.set_control_flow(ControlFlow::Poll),
+                    (_, Some(deadline)) => {
+                        target.set_control_flow(ControlFlow::WaitUntil(deadline))
+                    }
+                    (_, None) => target.set_control_flow(ControlFlow::Wait),
+                }
             }
             Event::LoopExiting => {
                 println!("\tLoopExiting");
@@ -149,14 +233,43 @@ pub fn run() -> Result<()> {
         }
 
         while let Some(idx) = windows.iter().position(|w| w.exiting()) {
+            // closing the last window needs a second request when --confirm-exit is set
+            let confirmed = pending_exit
+                .as_ref()
+                .is_some_and(|p| Instant::now() < p.deadline);
+            if args.confirm_exit && windows.len() == 1 && !confirmed {
+                let prompt = format!(
+                    "press Escape or close the window again within {}s to exit",
+                    CONFIRM_EXIT_TIMEOUT.as_secs()
+                );
+                println!("\t{}", prompt);
+
+                // show the prompt on screen too, the terminal may not be visible
+                let window = &mut windows[idx];
+                window.set_exiting(false);
+                pending_exit = Some(PendingExit {
+                    deadline: Instant::now() + CONFIRM_EXIT_TIMEOUT,
+                    window_id: window.id(),
+                    title: window.title(),
+                });
+                window.set_title(&prompt);
+                break;
+            }
             windows.remove(idx);
+            cancel_pending_exit(&mut pending_exit, &windows);
         }
 
         for cmd in q.drain() {
             match cmd {
                 WindowCmd::CreateWindow(title) => {
-                    if let Ok(window) = WindowBuilder::new().with_title(title).build(target) {
+                    // only the first window honors --fullscreen / --exclusive
+                    if let Ok(window) = WindowBuilder::new()
+                        .with_title(title)
+                        .with_fullscreen(initial_fullscreen.take())
+                        .build(target)
+                    {
                         windows.push(ZxWindow::new(shared_state.clone(), window));
+                        cancel_pending_exit(&mut pending_exit, &windows);
                     }
                 }
             }
@@ -167,6 +280,43 @@ pub fn run() -> Result<()> {
     event_loop.run(event_handler).map_err(|err| anyhow!(err))
 }
 
+fn cancel_pending_exit(pending_exit: &mut Option<PendingExit>, windows: &[ZxWindow]) {
+    if let Some(pending) = pending_exit.take() {
+        // put back the title the prompt replaced
+        if let Some(window) = windows.iter().find(|w| w.id() == pending.window_id) {
+            window.set_title(&pending.title);
+        }
+    }
+}
+
+fn initial_fullscreen_mode(args: &Args, target: &EventLoopWindowTarget<()>) -> Option<Fullscreen> {
+    if let Some((width, height)) = args.exclusive {
+        let monitor = target
+            .primary_monitor()
+            .or_else(|| target.available_monitors().next());
+        let mode = monitor.and_then(|m| {
+            m.video_modes()
+                .filter(|v| v.size().width == width && v.size().height == height)
+                .max_by_key(|v| v.refresh_rate_millihertz())
+        });
+        match mode {
+            Some(mode) => {
+                println!("\tstarting in exclusive fullscreen {:?}", mode);
+                return Some(Fullscreen::Exclusive(mode));
+            }
+            None => println!(
+                "\tno {}x{} video mode, falling back to borderless fullscreen",
+                width, height
+            ),
+        }
+    } else if !args.fullscreen {
+        return None;
+    }
+
+    println!("\tstarting in borderless fullscreen");
+    Some(Fullscreen::Borderless(None))
+}
+
 fn main() -> Result<()> {
-    run()
+    run(Args::parse()?)
 }
@@ -41,6 +41,22 @@ impl ZxWindow {
         self.is_exiting
     }
 
+    pub fn set_exiting(&mut self, exiting: bool) {
+        self.is_exiting = exiting;
+    }
+
+    pub fn request_redraw(&self) {
+        self.window.request_redraw();
+    }
+
+    pub fn title(&self) -> String {
+        self.window.title()
+    }
+
+    pub fn set_title(&self, title: &str) {
+        self.window.set_title(title);
+    }
+
     pub fn on_key_input(&mut self, code: KeyCode, pressed: bool, queue: &Rc<CmdQueue>) {
         println!(
             "# on_key {:?} - {}",